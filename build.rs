@@ -1,6 +1,6 @@
-use std::{env::var, fs, path::Path, str::FromStr};
+use std::{collections::BTreeMap, env::var, fs, path::Path, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 
 fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=migrations");
@@ -8,25 +8,48 @@ fn main() -> Result<()> {
     let cargo_manifest_dir = &var("CARGO_MANIFEST_DIR")?;
     let migrations_dir = Path::new(cargo_manifest_dir).join("migrations");
 
-    let mut latest_migration: usize = 0;
-    for entry in std::fs::read_dir(migrations_dir)? {
+    let mut migrations: BTreeMap<usize, String> = BTreeMap::new();
+    for entry in fs::read_dir(&migrations_dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_dir() {
-            let dir_id = path
-                .components()
-                .last()
-                .expect("no components found")
-                .as_os_str()
-                .to_str()
-                .expect("cannot convect to str")
-                .split("-")
-                .next()
-                .expect("invalid folder format");
-            let migration_id = usize::from_str(dir_id).expect("invalid migrations id format");
-            if migration_id > latest_migration {
-                latest_migration = migration_id;
-            }
+        if !path.is_dir() {
+            continue;
+        }
+
+        let dir_name = path
+            .components()
+            .next_back()
+            .expect("no components found")
+            .as_os_str()
+            .to_str()
+            .expect("cannot convert to str")
+            .to_string();
+
+        let (id_part, name_part) = dir_name.split_once('-').ok_or_else(|| {
+            anyhow!("malformed migration folder `{dir_name}`: expected `<id>-<name>`")
+        })?;
+        if name_part.is_empty() {
+            bail!("malformed migration folder `{dir_name}`: missing name after id");
+        }
+        let migration_id = usize::from_str(id_part).map_err(|_| {
+            anyhow!("malformed migration folder `{dir_name}`: `{id_part}` is not a valid id")
+        })?;
+
+        if let Some(existing) = migrations.insert(migration_id, dir_name.clone()) {
+            bail!("duplicate migration id {migration_id}: `{existing}` and `{dir_name}`");
+        }
+    }
+
+    let latest_migration = *migrations
+        .keys()
+        .next_back()
+        .ok_or_else(|| anyhow!("no migrations found in {}", migrations_dir.display()))?;
+
+    for expected_id in 1..=latest_migration {
+        if !migrations.contains_key(&expected_id) {
+            bail!(
+                "migrations are not contiguous: missing migration id {expected_id} (found up to {latest_migration})"
+            );
         }
     }
 
@@ -1,14 +1,21 @@
-use std::{cmp::max, collections::HashSet, path::PathBuf, str::FromStr};
+use std::{
+    cmp::max,
+    collections::HashSet,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::{anyhow, Result};
-use chrono::NaiveDate;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use home::home_dir;
 use include_dir::{include_dir, Dir};
 use lazy_static::lazy_static;
 use rusqlite::{functions::FunctionFlags, Connection};
 use rusqlite_migration::{Migrations, SchemaVersion};
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
 
 static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/migrations");
 lazy_static! {
@@ -35,9 +42,61 @@ fn add_not_undo_function(conn: &Connection, not_undo: bool) -> Result<()> {
     Ok(())
 }
 
+fn weekday_from_name(name: &str) -> Option<Weekday> {
+    match name {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Resolves the most recent occurrence of `weekday` strictly before `today`.
+fn most_recent_past_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let mut day = today - Duration::days(1);
+    while day.weekday() != weekday {
+        day -= Duration::days(1);
+    }
+    day
+}
+
+/// Parses relative/English date expressions against `today`: `now`/`today`,
+/// `yesterday`, `tomorrow`, `"<n> days/weeks ago"`, and weekday names
+/// (optionally preceded by `last`), resolving to their most recent past
+/// occurrence. Returns `None` if `date` doesn't match any of these forms.
+fn parse_relative_date(date: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let normalized = date.trim().to_lowercase();
+
+    match normalized.as_str() {
+        "now" | "today" => return Some(today),
+        "yesterday" => return Some(today - Duration::days(1)),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        _ => {}
+    }
+
+    for (unit, days_per_unit) in [("days ago", 1), ("day ago", 1), ("weeks ago", 7), ("week ago", 7)] {
+        if let Some(amount) = normalized.strip_suffix(unit) {
+            let amount: i64 = amount.trim().parse().ok()?;
+            return Some(today - Duration::days(amount * days_per_unit));
+        }
+    }
+
+    let weekday_name = normalized.strip_prefix("last ").unwrap_or(&normalized);
+    if let Some(weekday) = weekday_from_name(weekday_name) {
+        return Some(most_recent_past_weekday(today, weekday));
+    }
+
+    None
+}
+
 fn parse_date(date: &str) -> Result<NaiveDate> {
-    if date == "now" {
-        Ok(chrono::offset::Local::now().date_naive())
+    let today = chrono::offset::Local::now().date_naive();
+    if let Some(parsed) = parse_relative_date(date, today) {
+        Ok(parsed)
     } else {
         Ok(NaiveDate::parse_from_str(date, DATE_FORMAT)?)
     }
@@ -61,10 +120,23 @@ struct Cli {
     /// Path to database
     #[arg(long, default_value = default_db_path().into_os_string())]
     database: PathBuf,
+    /// Output format for displayed data
+    #[arg(long, value_enum, default_value = "table", global = true)]
+    format: OutputFormat,
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, ValueEnum, PartialEq)]
+enum OutputFormat {
+    /// Aligned, human-readable table
+    Table,
+    /// Comma-separated values, no header
+    Csv,
+    /// JSON array of objects (a single object for `balance`)
+    Json,
+}
+
 #[derive(Subcommand, PartialEq)]
 enum Commands {
     /// Migrate database to the latest version
@@ -82,16 +154,26 @@ enum Commands {
     },
     /// Delete database items
     Delete(DeleteArgs),
+    /// Import entries from a CSV or JSON file
+    Import(ImportArgs),
     /// Tail latest database entries
     Tail(TailArgs),
     /// Print out current hour balance
     Balance,
+    /// Report balances over a bounded, grouped window
+    Report(ReportArgs),
     /// Undo previous operation
     Undo {
         /// Number of changes to undo
         #[arg(default_value = "1")]
         depth: usize,
     },
+    /// Redo previously undone operation
+    Redo {
+        /// Number of changes to redo
+        #[arg(default_value = "1")]
+        depth: usize,
+    },
 }
 
 #[derive(Clone, ValueEnum, PartialEq)]
@@ -117,6 +199,44 @@ struct DeleteArgs {
     select: DBSelectGroup,
 }
 
+#[derive(Clone, Copy, ValueEnum, PartialEq)]
+enum ImportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, PartialEq)]
+struct ImportArgs {
+    /// Path to the CSV or JSON file to import, with "date" and "hours" columns
+    path: PathBuf,
+    /// File format, inferred from the file extension if omitted
+    #[arg(long, value_enum)]
+    import_format: Option<ImportFormat>,
+}
+
+#[derive(Clone, Copy, ValueEnum, PartialEq)]
+enum ReportGroupBy {
+    /// One bucket per calendar day
+    Day,
+    /// One bucket per ISO week (starting Monday)
+    Week,
+    /// One bucket per calendar month
+    Month,
+}
+
+#[derive(Args, PartialEq)]
+struct ReportArgs {
+    /// Start of the reporting window, inclusive
+    #[arg(long, value_parser = parse_date)]
+    from: Option<NaiveDate>,
+    /// End of the reporting window, inclusive
+    #[arg(long, value_parser = parse_date)]
+    to: Option<NaiveDate>,
+    /// Bucket granularity for per-period sums
+    #[arg(long, value_enum, default_value = "day")]
+    group_by: ReportGroupBy,
+}
+
 #[derive(Args, Clone, Debug, PartialEq)]
 #[group(required = true, multiple = false)]
 struct DBSelectGroup {
@@ -157,8 +277,21 @@ fn schema(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-fn add(conn: &Connection, date: NaiveDate, time: Decimal) -> Result<()> {
-    let date_string = date.format("%Y-%m-%d").to_string();
+/// Opens a new undo barrier and returns its id. Every CLI invocation that
+/// mutates `hours` starts one so its writes are grouped into a single
+/// `undo()`/`Redo` step, regardless of how many rows they touch.
+///
+/// A fresh barrier also retires any pending `redolog` entries: once a new
+/// change has been made, the previously undone history it would redo is
+/// stale and must not be resurrected by a later `redo`.
+fn new_barrier(conn: &Connection) -> Result<i64> {
+    conn.execute("UPDATE redolog SET processed = 1 WHERE processed = 0", ())?;
+    conn.execute("INSERT INTO undo_barrier DEFAULT VALUES", ())?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn insert_entry(conn: &Connection, date: NaiveDate, time: Decimal) -> Result<()> {
+    let date_string = date.format(DATE_FORMAT).to_string();
     conn.execute(
         "INSERT INTO hours (date, time, deleted) VALUES (?1, ?2, 0)",
         (&date_string, time.to_string()),
@@ -166,88 +299,387 @@ fn add(conn: &Connection, date: NaiveDate, time: Decimal) -> Result<()> {
     Ok(())
 }
 
-fn tail_entry(conn: &Connection, n: usize) -> Result<()> {
-    let mut statement = conn.prepare(&format!(
-        "SELECT entry_id, date, time FROM hours WHERE deleted = 0 ORDER BY entry_id DESC LIMIT {}",
-        n
-    ))?;
+fn add(conn: &mut Connection, date: NaiveDate, time: Decimal) -> Result<()> {
+    let tx = conn.transaction()?;
+    new_barrier(&tx)?;
+    insert_entry(&tx, date, time)?;
+    tx.commit()?;
+    Ok(())
+}
 
-    let entry_iter = statement.query_map([], |row| {
-        Ok((
-            row.get::<usize, usize>(0)?,
-            row.get::<usize, String>(1)?,
-            row.get::<usize, f64>(2)?,
-        ))
-    })?;
+#[derive(Deserialize)]
+struct ImportRecord {
+    date: String,
+    hours: String,
+}
+
+fn import_format(args: &ImportArgs) -> Result<ImportFormat> {
+    if let Some(format) = args.import_format {
+        return Ok(format);
+    }
+    match args.path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => Ok(ImportFormat::Csv),
+        Some("json") => Ok(ImportFormat::Json),
+        _ => Err(anyhow!(
+            "cannot infer import format from file extension, pass --import-format"
+        )),
+    }
+}
 
-    println!("{:>10} {:>10} {:>10}", "ID", "Date", "Time");
-    for entry in entry_iter {
-        let (entry_id, date, time) = entry.expect("failed to read row");
-        println!("{:>10} {:>10} {:>10.1}", entry_id, date, time);
+fn read_import_records(path: &Path, format: ImportFormat) -> Result<Vec<ImportRecord>> {
+    let file = std::fs::File::open(path)?;
+    match format {
+        ImportFormat::Csv => csv::Reader::from_reader(file)
+            .into_deserialize()
+            .collect::<Result<Vec<ImportRecord>, _>>()
+            .map_err(Into::into),
+        ImportFormat::Json => Ok(serde_json::from_reader(file)?),
     }
+}
 
+/// Imports rows from a CSV or JSON file (`date`/`hours` columns) into
+/// `hours` in a single transaction: each row is parsed and validated
+/// through [`parse_date`]/[`parse_non_zero`] and then inserted, and the
+/// transaction is committed only after every row succeeds, so a malformed
+/// row leaves the database untouched.
+fn import(conn: &mut Connection, args: ImportArgs) -> Result<()> {
+    let format = import_format(&args)?;
+    let records = read_import_records(&args.path, format)?;
+
+    let tx = conn.transaction()?;
+    new_barrier(&tx)?;
+    for (i, record) in records.iter().enumerate() {
+        let record_num = i + 1;
+        let date = parse_date(&record.date).map_err(|e| anyhow!("record {record_num}: {e}"))?;
+        let time = parse_non_zero(&record.hours).map_err(|e| anyhow!("record {record_num}: {e}"))?;
+        insert_entry(&tx, date, time)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct EntryRow {
+    entry_id: usize,
+    date: String,
+    time: Decimal,
+}
+
+#[derive(Serialize)]
+struct DateRow {
+    date: String,
+    time: Decimal,
+}
+
+fn print_entry_rows(rows: &[EntryRow], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let id_width = rows.iter().fold("ID".len(), |w, r| {
+                max(w, r.entry_id.to_string().len())
+            });
+            let date_width = rows
+                .iter()
+                .fold("Date".len(), |w, r| max(w, r.date.len()));
+            let time_width = rows
+                .iter()
+                .fold("Time".len(), |w, r| max(w, r.time.to_string().len()));
+
+            println!(
+                "{:>id_width$} {:>date_width$} {:>time_width$}",
+                "ID", "Date", "Time"
+            );
+            for row in rows {
+                println!(
+                    "{:>id_width$} {:>date_width$} {}",
+                    row.entry_id,
+                    row.date,
+                    colorize_time(row.time, time_width)
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            for row in rows {
+                println!("{},{},{}", row.entry_id, row.date, row.time);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(rows)?),
+    }
+    Ok(())
+}
+
+fn print_date_rows(rows: &[DateRow], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let date_width = rows
+                .iter()
+                .fold("Date".len(), |w, r| max(w, r.date.len()));
+            let time_width = rows
+                .iter()
+                .fold("Time".len(), |w, r| max(w, r.time.to_string().len()));
+
+            println!("{:>date_width$} {:>time_width$}", "Date", "Time");
+            for row in rows {
+                println!(
+                    "{:>date_width$} {}",
+                    row.date,
+                    colorize_time(row.time, time_width)
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            for row in rows {
+                println!("{},{}", row.date, row.time);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(rows)?),
+    }
     Ok(())
 }
 
-fn tail_date(conn: &Connection, n: usize) -> Result<()> {
+/// Colors a balance-like value green/red when positive/negative, right-aligned
+/// to `width`. No-op (plain padding) when stdout isn't a terminal.
+fn colorize_time(time: Decimal, width: usize) -> String {
+    let text = format!("{:>width$}", time, width = width);
+    if !std::io::stdout().is_terminal() {
+        return text;
+    }
+    if time > Decimal::ZERO {
+        format!("\x1b[32m{}\x1b[0m", text)
+    } else if time < Decimal::ZERO {
+        format!("\x1b[31m{}\x1b[0m", text)
+    } else {
+        text
+    }
+}
+
+fn tail_entry(conn: &Connection, n: usize, format: OutputFormat) -> Result<()> {
+    let mut statement = conn.prepare(
+        "SELECT entry_id, date, time FROM hours WHERE deleted = 0 ORDER BY entry_id DESC LIMIT ?1",
+    )?;
+
+    let rows = statement
+        .query_map([n], |row| {
+            Ok(EntryRow {
+                entry_id: row.get(0)?,
+                date: row.get(1)?,
+                time: Decimal::from_str(&row.get::<usize, String>(2)?)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    print_entry_rows(&rows, format)
+}
+
+fn tail_date(conn: &Connection, n: usize, format: OutputFormat) -> Result<()> {
     let mut statement =
         conn.prepare("SELECT date, time FROM hours WHERE deleted = 0 ORDER BY date DESC")?;
-    let mut rows = statement.query([])?;
+    let mut sql_rows = statement.query([])?;
 
-    let first_row = if let Some(row) = rows.next()? {
+    let first_row = if let Some(row) = sql_rows.next()? {
         row
     } else {
         return Ok(());
     };
 
     let mut date = NaiveDate::parse_from_str(&first_row.get::<usize, String>(0)?, DATE_FORMAT)?;
-    let mut time_sum = first_row.get::<usize, f64>(1)?;
-
-    println!("{:>10} {:>10}", "Date", "Time");
+    let mut time_sum = Decimal::from_str(&first_row.get::<usize, String>(1)?)?;
 
+    let mut rows = Vec::new();
     let mut count = 1;
 
-    while let Some(row) = rows.next()? {
+    while let Some(row) = sql_rows.next()? {
         let next_date = NaiveDate::parse_from_str(&row.get::<usize, String>(0)?, DATE_FORMAT)?;
-        let next_time = row.get::<usize, f64>(1)?;
+        let next_time = Decimal::from_str(&row.get::<usize, String>(1)?)?;
 
         if next_date == date {
             time_sum += next_time;
             continue;
         }
 
-        println!("{:>10} {:>10.1}", date.format(DATE_FORMAT), time_sum);
+        rows.push(DateRow {
+            date: date.format(DATE_FORMAT).to_string(),
+            time: time_sum,
+        });
         date = next_date;
         time_sum = next_time;
         count += 1;
 
         if count > n {
-            return Ok(());
+            return print_date_rows(&rows, format);
         }
     }
-    println!("{:>10} {:>10.1}", date.format(DATE_FORMAT), time_sum);
+    rows.push(DateRow {
+        date: date.format(DATE_FORMAT).to_string(),
+        time: time_sum,
+    });
 
-    Ok(())
+    print_date_rows(&rows, format)
 }
 
-fn tail(conn: &Connection, tail_args: TailArgs) -> Result<()> {
+fn tail(conn: &Connection, tail_args: TailArgs, format: OutputFormat) -> Result<()> {
     match tail_args.command {
-        TailCommands::Entry => tail_entry(conn, tail_args.n)?,
-        TailCommands::Date => tail_date(conn, tail_args.n)?,
+        TailCommands::Entry => tail_entry(conn, tail_args.n, format)?,
+        TailCommands::Date => tail_date(conn, tail_args.n, format)?,
     };
     Ok(())
 }
 
-fn balance(conn: &Connection) -> Result<()> {
+fn balance(conn: &Connection, format: OutputFormat) -> Result<()> {
     let time: f64 = conn.query_row("SELECT TOTAL(time) FROM hours WHERE deleted = 0", (), |r| {
         r.get(0)
     })?;
-    println!("Total hour balance: {:.1}", time);
+    let time = Decimal::from_f64(time).ok_or_else(|| anyhow!("balance is not a finite number"))?;
+
+    match format {
+        OutputFormat::Table => println!("Total hour balance: {}", colorize_time(time, 0)),
+        OutputFormat::Csv => println!("{}", time),
+        OutputFormat::Json => println!("{}", serde_json::json!({ "time": time })),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ReportRow {
+    period: String,
+    time: Decimal,
+    balance: Decimal,
+}
+
+/// Start of the bucket `date` falls into for the given granularity.
+fn bucket_start(date: NaiveDate, group_by: ReportGroupBy) -> NaiveDate {
+    match group_by {
+        ReportGroupBy::Day => date,
+        ReportGroupBy::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        ReportGroupBy::Month => date.with_day(1).expect("day 1 is always valid"),
+    }
+}
+
+fn bucket_label(start: NaiveDate, group_by: ReportGroupBy) -> String {
+    match group_by {
+        ReportGroupBy::Day | ReportGroupBy::Week => start.format(DATE_FORMAT).to_string(),
+        ReportGroupBy::Month => start.format("%Y-%m").to_string(),
+    }
+}
+
+fn print_report_rows(rows: &[ReportRow], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            let period_width = rows
+                .iter()
+                .fold("Period".len(), |w, r| max(w, r.period.len()));
+            let time_width = rows
+                .iter()
+                .fold("Time".len(), |w, r| max(w, r.time.to_string().len()));
+            let balance_width = rows
+                .iter()
+                .fold("Balance".len(), |w, r| max(w, r.balance.to_string().len()));
+
+            println!(
+                "{:>period_width$} {:>time_width$} {:>balance_width$}",
+                "Period", "Time", "Balance"
+            );
+            for row in rows {
+                println!(
+                    "{:>period_width$} {:>time_width$} {}",
+                    row.period,
+                    row.time,
+                    colorize_time(row.balance, balance_width)
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            for row in rows {
+                println!("{},{},{}", row.period, row.time, row.balance);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(rows)?),
+    }
     Ok(())
 }
 
+/// Sums `time` for every non-deleted entry strictly before `before`, i.e.
+/// the account's true cumulative balance at the start of a reporting window.
+fn balance_before(conn: &Connection, before: NaiveDate) -> Result<Decimal> {
+    let time: f64 = conn.query_row(
+        "SELECT TOTAL(time) FROM hours WHERE deleted = 0 AND date < ?1",
+        [before.format(DATE_FORMAT).to_string()],
+        |r| r.get(0),
+    )?;
+    Decimal::from_f64(time).ok_or_else(|| anyhow!("balance is not a finite number"))
+}
+
+/// Computes the grouped, running-balance rows for `report()`: the SQL-driven
+/// counterpart to the pure `bucket_start`/`bucket_label` helpers, split out
+/// so it can be exercised against a real database without printing.
+fn report_rows(conn: &Connection, report_args: &ReportArgs) -> Result<Vec<ReportRow>> {
+    let mut conditions = vec!["deleted = 0".to_string()];
+    let mut params = Vec::new();
+    if let Some(from) = report_args.from {
+        conditions.push(format!("date >= ?{}", params.len() + 1));
+        params.push(from.format(DATE_FORMAT).to_string());
+    }
+    if let Some(to) = report_args.to {
+        conditions.push(format!("date <= ?{}", params.len() + 1));
+        params.push(to.format(DATE_FORMAT).to_string());
+    }
+
+    let mut statement = conn.prepare(&format!(
+        "SELECT date, time FROM hours WHERE {} ORDER BY date ASC",
+        conditions.join(" AND ")
+    ))?;
+    let mut rows = statement.query(rusqlite::params_from_iter(params))?;
+
+    let mut report_rows = Vec::new();
+    let mut running_balance = match report_args.from {
+        Some(from) => balance_before(conn, from)?,
+        None => Decimal::ZERO,
+    };
+    let mut current_bucket: Option<NaiveDate> = None;
+    let mut bucket_sum = Decimal::ZERO;
+
+    while let Some(row) = rows.next()? {
+        let date = NaiveDate::parse_from_str(&row.get::<usize, String>(0)?, DATE_FORMAT)?;
+        let time = Decimal::from_str(&row.get::<usize, String>(1)?)?;
+        let start = bucket_start(date, report_args.group_by);
+
+        match current_bucket {
+            Some(bucket) if bucket == start => bucket_sum += time,
+            Some(bucket) => {
+                running_balance += bucket_sum;
+                report_rows.push(ReportRow {
+                    period: bucket_label(bucket, report_args.group_by),
+                    time: bucket_sum,
+                    balance: running_balance,
+                });
+                current_bucket = Some(start);
+                bucket_sum = time;
+            }
+            None => {
+                current_bucket = Some(start);
+                bucket_sum = time;
+            }
+        }
+    }
+    if let Some(bucket) = current_bucket {
+        running_balance += bucket_sum;
+        report_rows.push(ReportRow {
+            period: bucket_label(bucket, report_args.group_by),
+            time: bucket_sum,
+            balance: running_balance,
+        });
+    }
+
+    Ok(report_rows)
+}
+
+fn report(conn: &Connection, report_args: ReportArgs, format: OutputFormat) -> Result<()> {
+    print_report_rows(&report_rows(conn, &report_args)?, format)
+}
+
 fn delete_entry(conn: &mut Connection, ids: HashSet<usize>) -> Result<()> {
     let tx = conn.transaction()?;
+    new_barrier(&tx)?;
     {
         let mut statement = tx.prepare("UPDATE hours SET deleted = 1 WHERE entry_id = ?1")?;
         for i in ids {
@@ -260,6 +692,7 @@ fn delete_entry(conn: &mut Connection, ids: HashSet<usize>) -> Result<()> {
 
 fn delete_date(conn: &mut Connection, dates: HashSet<NaiveDate>) -> Result<()> {
     let tx = conn.transaction()?;
+    new_barrier(&tx)?;
     {
         let mut statement = tx.prepare("UPDATE hours SET deleted = 1 WHERE date = ?1")?;
         for d in dates {
@@ -287,37 +720,135 @@ fn delete(conn: &mut Connection, delete_args: DeleteArgs) -> Result<()> {
 struct UndoRow {
     row_id: usize,
     entry_id: usize,
-    deleted_old: bool,
-    processed: bool,
-}
-
-fn undo(conn: &Connection, depth: usize) -> Result<()> {
-    dbg!(&depth);
-    let mut statement = conn
-        .prepare("SELECT row_id, entry_id, deleted_old, processed FROM undolog ORDER BY row_id")?;
-    let mut rows = statement.query_map([], |row| {
-        Ok(UndoRow {
-            row_id: row.get(0)?,
-            entry_id: row.get(1)?,
-            deleted_old: row.get(2)?,
-            processed: row.get(3)?,
-        })
-    })?;
+    deleted_old: Option<bool>,
+    date: Option<String>,
+    time: Option<String>,
+}
 
-    let mut first_found = false;
+/// Walks `depth` barriers worth of `table` entries, replaying each row
+/// against `hours` and recording the inverse of that replay into
+/// `other_table` so it can be walked again by the opposite operation.
+/// Shared by `undo()` and `redo()`, which only differ in which log they
+/// consume from, which log they feed, and how a `deleted_old: NULL` row
+/// (an insert, from `is_redo`'s point of view) is replayed: undo reverses
+/// it by deleting the row, redo reverses it by re-inserting it.
+fn undo_barriers(
+    conn: &mut Connection,
+    table: &str,
+    other_table: &str,
+    is_redo: bool,
+    depth: usize,
+) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    // Barriers are grouped by when they were appended to `table`, not by
+    // their numeric id, so a redo correctly replays the most recently
+    // undone barrier first even if it undid an older barrier_id.
+    let barrier_ids: Vec<i64> = {
+        let mut statement = tx.prepare(&format!(
+            "SELECT barrier_id FROM {table} WHERE processed = 0 \
+             GROUP BY barrier_id ORDER BY MAX(row_id) DESC LIMIT ?1"
+        ))?;
+        let ids = statement.query_map([depth], |row| row.get(0))?;
+        ids.collect::<rusqlite::Result<_>>()?
+    };
 
-    while let Some(row) = rows.next() {
-        let row = row?;
-        dbg!(&row);
+    for barrier_id in barrier_ids {
+        let rows: Vec<UndoRow> = {
+            let mut statement = tx.prepare(&format!(
+                "SELECT row_id, entry_id, deleted_old, date, time FROM {table} \
+                 WHERE barrier_id = ?1 AND processed = 0 ORDER BY row_id DESC"
+            ))?;
+            let rows = statement.query_map([barrier_id], |row| {
+                Ok(UndoRow {
+                    row_id: row.get(0)?,
+                    entry_id: row.get(1)?,
+                    deleted_old: row.get(2)?,
+                    date: row.get(3)?,
+                    time: row.get(4)?,
+                })
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
 
-        if row.processed == false {
-            first_found = true
+        for row in rows {
+            match row.deleted_old {
+                None if is_redo => {
+                    // A redo of an insert: the row was hard-deleted by the
+                    // undo it reverses, so bring it back. Keep its date/time
+                    // in the log too, so if this redo is itself later undone
+                    // and redone again, that next hop still has the data to
+                    // re-insert instead of hitting `hours`'s NOT NULL columns
+                    // with NULLs.
+                    tx.execute(
+                        "INSERT INTO hours (entry_id, date, time, deleted) \
+                         VALUES (?1, ?2, ?3, 0)",
+                        (row.entry_id, row.date.clone(), row.time.clone()),
+                    )?;
+                    tx.execute(
+                        &format!(
+                            "INSERT INTO {other_table} \
+                             (entry_id, deleted_old, date, time, barrier_id) \
+                             VALUES (?1, NULL, ?2, ?3, ?4)"
+                        ),
+                        (row.entry_id, row.date, row.time, barrier_id),
+                    )?;
+                }
+                None => {
+                    // An undo of an insert: reverse by hard-deleting it,
+                    // keeping its date/time so a later redo can re-insert it.
+                    tx.execute("DELETE FROM hours WHERE entry_id = ?1", [row.entry_id])?;
+                    tx.execute(
+                        &format!(
+                            "INSERT INTO {other_table} \
+                             (entry_id, deleted_old, date, time, barrier_id) \
+                             VALUES (?1, NULL, ?2, ?3, ?4)"
+                        ),
+                        (row.entry_id, row.date, row.time, barrier_id),
+                    )?;
+                }
+                Some(deleted_old) => {
+                    // Row's `deleted` flag was changed: restore the old
+                    // value, logging the value it held just before this so
+                    // the opposite operation can restore it in turn.
+                    let previous_deleted: bool = tx.query_row(
+                        "SELECT deleted FROM hours WHERE entry_id = ?1",
+                        [row.entry_id],
+                        |r| r.get(0),
+                    )?;
+                    tx.execute(
+                        "UPDATE hours SET deleted = ?1 WHERE entry_id = ?2",
+                        (deleted_old, row.entry_id),
+                    )?;
+                    tx.execute(
+                        &format!(
+                            "INSERT INTO {other_table} \
+                             (entry_id, deleted_old, barrier_id) VALUES (?1, ?2, ?3)"
+                        ),
+                        (row.entry_id, previous_deleted, barrier_id),
+                    )?;
+                }
+            }
+
+            tx.execute(
+                &format!("UPDATE {table} SET processed = 1 WHERE row_id = ?1"),
+                [row.row_id],
+            )?;
         }
     }
 
+    tx.commit()?;
     Ok(())
 }
 
+fn undo(conn: &mut Connection, depth: usize) -> Result<()> {
+    undo_barriers(conn, "undolog", "redolog", false, depth)
+}
+
+fn redo(conn: &mut Connection, depth: usize) -> Result<()> {
+    undo_barriers(conn, "redolog", "undolog", true, depth)
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -335,16 +866,25 @@ fn main() -> Result<()> {
         return Err(anyhow!("Database is not up to date with the latest schema"));
     }
 
-    add_not_undo_function(&conn, !(matches!(cli.command, Commands::Undo { depth: _ })))?;
+    add_not_undo_function(
+        &conn,
+        !matches!(
+            cli.command,
+            Commands::Undo { depth: _ } | Commands::Redo { depth: _ }
+        ),
+    )?;
 
     match cli.command {
         Commands::Migrate => todo!(),
         Commands::Schema => todo!(),
-        Commands::Add { date, time } => add(&conn, date, time)?,
-        Commands::Tail(tail_args) => tail(&conn, tail_args)?,
-        Commands::Balance => balance(&conn)?,
+        Commands::Add { date, time } => add(&mut conn, date, time)?,
+        Commands::Tail(tail_args) => tail(&conn, tail_args, cli.format)?,
+        Commands::Balance => balance(&conn, cli.format)?,
+        Commands::Report(report_args) => report(&conn, report_args, cli.format)?,
         Commands::Delete(delete_args) => delete(&mut conn, delete_args)?,
-        Commands::Undo { depth } => undo(&conn, depth)?,
+        Commands::Import(import_args) => import(&mut conn, import_args)?,
+        Commands::Undo { depth } => undo(&mut conn, depth)?,
+        Commands::Redo { depth } => redo(&mut conn, depth)?,
     }
 
     Ok(())
@@ -358,4 +898,252 @@ mod tests {
     fn migrations_test() {
         assert!(MIGRATIONS.validate().is_ok());
     }
+
+    #[test]
+    fn import_format_flag_does_not_collide_with_global_format() {
+        // Regression test: `ImportArgs` used to reuse the arg id `format`,
+        // the same id as the global `--format` flag, which made clap panic
+        // while downcasting the value to the wrong `ValueEnum` type.
+        let cli = Cli::try_parse_from([
+            "kouhia",
+            "import",
+            "entries.csv",
+            "--import-format",
+            "csv",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Commands::Import(_)));
+    }
+
+    #[test]
+    fn parse_relative_date_handles_now_today_yesterday_tomorrow() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        assert_eq!(parse_relative_date("now", today), Some(today));
+        assert_eq!(parse_relative_date("Today", today), Some(today));
+        assert_eq!(
+            parse_relative_date("yesterday", today),
+            Some(today - Duration::days(1))
+        );
+        assert_eq!(
+            parse_relative_date("tomorrow", today),
+            Some(today + Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn parse_relative_date_handles_n_days_and_weeks_ago() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        assert_eq!(
+            parse_relative_date("3 days ago", today),
+            Some(today - Duration::days(3))
+        );
+        assert_eq!(
+            parse_relative_date("1 day ago", today),
+            Some(today - Duration::days(1))
+        );
+        assert_eq!(
+            parse_relative_date("2 weeks ago", today),
+            Some(today - Duration::days(14))
+        );
+        assert_eq!(
+            parse_relative_date("1 week ago", today),
+            Some(today - Duration::days(7))
+        );
+    }
+
+    #[test]
+    fn parse_relative_date_handles_weekday_names() {
+        // 2026-07-28 is a Tuesday, so the most recent Monday is 2026-07-27
+        // and the most recent (last) Friday is 2026-07-24.
+        let today = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        assert_eq!(
+            parse_relative_date("monday", today),
+            Some(NaiveDate::from_ymd_opt(2026, 7, 27).unwrap())
+        );
+        assert_eq!(
+            parse_relative_date("last fri", today),
+            Some(NaiveDate::from_ymd_opt(2026, 7, 24).unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_relative_date_rejects_unrecognized_input() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        assert_eq!(parse_relative_date("2026-07-28", today), None);
+        assert_eq!(parse_relative_date("whenever", today), None);
+    }
+
+    #[test]
+    fn bucket_start_day_is_the_date_itself() {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        assert_eq!(bucket_start(date, ReportGroupBy::Day), date);
+    }
+
+    #[test]
+    fn bucket_start_week_rounds_down_to_monday() {
+        // 2026-07-28 is a Tuesday.
+        let date = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 7, 27).unwrap();
+        assert_eq!(bucket_start(date, ReportGroupBy::Week), monday);
+        assert_eq!(bucket_start(monday, ReportGroupBy::Week), monday);
+    }
+
+    #[test]
+    fn bucket_start_month_rounds_down_to_first() {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 28).unwrap();
+        let first = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        assert_eq!(bucket_start(date, ReportGroupBy::Month), first);
+    }
+
+    #[test]
+    fn bucket_label_formats_by_granularity() {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        assert_eq!(bucket_label(date, ReportGroupBy::Day), "2026-07-01");
+        assert_eq!(bucket_label(date, ReportGroupBy::Week), "2026-07-01");
+        assert_eq!(bucket_label(date, ReportGroupBy::Month), "2026-07");
+    }
+
+    /// Opens a migrated in-memory database for undo/redo/report integration tests.
+    fn test_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    /// Non-deleted `hours` rows as `(date, time)`, ordered by `entry_id` ascending.
+    fn live_entries(conn: &Connection) -> Vec<(NaiveDate, Decimal)> {
+        let mut statement = conn
+            .prepare("SELECT date, time FROM hours WHERE deleted = 0 ORDER BY entry_id ASC")
+            .unwrap();
+        statement
+            .query_map((), |row| {
+                let date: String = row.get(0)?;
+                let time: String = row.get(1)?;
+                Ok((
+                    NaiveDate::parse_from_str(&date, DATE_FORMAT).unwrap(),
+                    Decimal::from_str(&time).unwrap(),
+                ))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn add_undo_redo_roundtrip_survives_repeated_cycles() {
+        let mut conn = test_db();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let time = Decimal::from_str("1.5").unwrap();
+
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, date, time).unwrap();
+        assert_eq!(live_entries(&conn), vec![(date, time)]);
+
+        // Repeated undo/redo cycles must keep reproducing the same entry,
+        // not just the first one (regression for the redo-of-insert data loss).
+        for _ in 0..3 {
+            add_not_undo_function(&conn, false).unwrap();
+            undo(&mut conn, 1).unwrap();
+            assert_eq!(live_entries(&conn), vec![]);
+
+            add_not_undo_function(&conn, false).unwrap();
+            redo(&mut conn, 1).unwrap();
+            assert_eq!(live_entries(&conn), vec![(date, time)]);
+        }
+    }
+
+    #[test]
+    fn delete_undo_redo_roundtrip() {
+        let mut conn = test_db();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        let time = Decimal::from_str("2").unwrap();
+
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, date, time).unwrap();
+        let entry_id: usize = conn
+            .query_row("SELECT entry_id FROM hours", (), |r| r.get(0))
+            .unwrap();
+
+        add_not_undo_function(&conn, true).unwrap();
+        delete_entry(&mut conn, HashSet::from([entry_id])).unwrap();
+        assert_eq!(live_entries(&conn), vec![]);
+
+        add_not_undo_function(&conn, false).unwrap();
+        undo(&mut conn, 1).unwrap();
+        assert_eq!(live_entries(&conn), vec![(date, time)]);
+
+        add_not_undo_function(&conn, false).unwrap();
+        redo(&mut conn, 1).unwrap();
+        assert_eq!(live_entries(&conn), vec![]);
+    }
+
+    #[test]
+    fn undo_depth_walks_multiple_barriers_in_reverse_order() {
+        let mut conn = test_db();
+        let first = (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), Decimal::from_str("1").unwrap());
+        let second = (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), Decimal::from_str("2").unwrap());
+
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, first.0, first.1).unwrap();
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, second.0, second.1).unwrap();
+        assert_eq!(live_entries(&conn), vec![first, second]);
+
+        add_not_undo_function(&conn, false).unwrap();
+        undo(&mut conn, 2).unwrap();
+        assert_eq!(live_entries(&conn), vec![]);
+
+        add_not_undo_function(&conn, false).unwrap();
+        redo(&mut conn, 2).unwrap();
+        assert_eq!(live_entries(&conn), vec![first, second]);
+    }
+
+    #[test]
+    fn report_rows_seeds_balance_from_before_the_window() {
+        let mut conn = test_db();
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), Decimal::from_str("10").unwrap()).unwrap();
+        add_not_undo_function(&conn, true).unwrap();
+        add(&mut conn, NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(), Decimal::from_str("2").unwrap()).unwrap();
+
+        let args = ReportArgs {
+            from: Some(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()),
+            to: Some(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()),
+            group_by: ReportGroupBy::Day,
+        };
+        let rows = report_rows(&conn, &args).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].period, "2026-07-01");
+        assert_eq!(rows[0].time, Decimal::from_str("2").unwrap());
+        assert_eq!(rows[0].balance, Decimal::from_str("12").unwrap());
+    }
+
+    #[test]
+    fn report_rows_groups_by_week_and_accumulates_balance() {
+        let mut conn = test_db();
+        for (date, time) in [
+            (NaiveDate::from_ymd_opt(2026, 7, 27).unwrap(), "1"), // Monday
+            (NaiveDate::from_ymd_opt(2026, 7, 28).unwrap(), "2"), // Tuesday, same week
+            (NaiveDate::from_ymd_opt(2026, 8, 3).unwrap(), "4"),  // following Monday
+        ] {
+            add_not_undo_function(&conn, true).unwrap();
+            add(&mut conn, date, Decimal::from_str(time).unwrap()).unwrap();
+        }
+
+        let args = ReportArgs {
+            from: None,
+            to: None,
+            group_by: ReportGroupBy::Week,
+        };
+        let rows = report_rows(&conn, &args).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].period, "2026-07-27");
+        assert_eq!(rows[0].time, Decimal::from_str("3").unwrap());
+        assert_eq!(rows[0].balance, Decimal::from_str("3").unwrap());
+        assert_eq!(rows[1].period, "2026-08-03");
+        assert_eq!(rows[1].time, Decimal::from_str("4").unwrap());
+        assert_eq!(rows[1].balance, Decimal::from_str("7").unwrap());
+    }
 }